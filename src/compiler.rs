@@ -0,0 +1,99 @@
+pub mod error;
+pub mod mangle;
+
+use std::collections::HashMap;
+
+use inkwell::builder::Builder;
+use inkwell::context::Context;
+use inkwell::module::Module;
+use inkwell::values::{FunctionValue, PointerValue};
+use rustpython_parser::ast;
+
+use crate::irgen::stmt::CGStmt;
+use crate::value::ValueType;
+use error::{CompilerErrorReport, CompilerErrorType};
+
+/// Per-function variable scope: name -> (declared type, stack slot).
+pub type Scope<'ctx> = HashMap<String, (ValueType, PointerValue<'ctx>)>;
+
+pub struct Compiler<'a, 'ctx> {
+    pub name: String,
+    pub context: &'ctx Context,
+    pub builder: &'a Builder<'ctx>,
+    pub module: &'a Module<'ctx>,
+    pub variables: Scope<'ctx>,
+    pub fn_value_opt: Option<FunctionValue<'ctx>>,
+    pub fn_scope: HashMap<FunctionValue<'ctx>, Scope<'ctx>>,
+    pub current_source_location: ast::Location,
+    functions: HashMap<String, FunctionValue<'ctx>>,
+    /// Declared parameter names, in signature order, keyed by the function
+    /// they belong to. Populated by `register_function` alongside `functions`
+    /// so keyword-argument calls can resolve a name to its positional slot.
+    function_params: HashMap<FunctionValue<'ctx>, Vec<String>>,
+}
+
+impl<'a, 'ctx> Compiler<'a, 'ctx> {
+    pub fn new(
+        name: String,
+        context: &'ctx Context,
+        builder: &'a Builder<'ctx>,
+        module: &'a Module<'ctx>,
+    ) -> Self {
+        Compiler {
+            name,
+            context,
+            builder,
+            module,
+            variables: HashMap::new(),
+            fn_value_opt: None,
+            fn_scope: HashMap::new(),
+            current_source_location: ast::Location::new(1, 1),
+            functions: HashMap::new(),
+            function_params: HashMap::new(),
+        }
+    }
+
+    /// Lowers every top-level statement of the parsed program into the
+    /// module; statement codegen lives in `irgen::stmt`.
+    pub fn compile(&mut self, program: ast::Program) {
+        for statement in program.statements {
+            self.compile_stmt(&statement);
+        }
+    }
+
+    pub fn set_source_location(&mut self, location: ast::Location) {
+        self.current_source_location = location;
+    }
+
+    pub fn fn_value(&self) -> FunctionValue<'ctx> {
+        self.fn_value_opt
+            .expect("fn_value() called outside of a function body")
+    }
+
+    pub fn get_function(&self, name: &str) -> Option<FunctionValue<'ctx>> {
+        self.functions.get(name).copied()
+    }
+
+    /// Registers a compiled function under `name`, recording its declared
+    /// parameter names (in order) so calls can later resolve keyword
+    /// arguments back to a positional slot via `get_param_names`.
+    pub fn register_function(
+        &mut self,
+        name: String,
+        func: FunctionValue<'ctx>,
+        param_names: Vec<String>,
+    ) {
+        self.functions.insert(name, func);
+        self.function_params.insert(func, param_names);
+    }
+
+    pub fn get_param_names(&self, func: FunctionValue<'ctx>) -> Option<Vec<String>> {
+        self.function_params.get(&func).cloned()
+    }
+}
+
+impl<'a, 'ctx> CompilerErrorReport for Compiler<'a, 'ctx> {
+    fn errs(&self, err: CompilerErrorType) -> String {
+        format!("{:?}\n{:?}", self.current_source_location, err)
+    }
+}
@@ -0,0 +1,13 @@
+/// Errors surfaced during codegen, rendered alongside the offending
+/// source location by [`CompilerErrorReport::errs`].
+#[derive(Debug)]
+pub enum CompilerErrorType<'a> {
+    NameError(&'a str),
+    TypeError(String),
+    IndexError(String),
+    ArgumentError(String),
+}
+
+pub trait CompilerErrorReport {
+    fn errs(&self, err: CompilerErrorType) -> String;
+}
@@ -0,0 +1,46 @@
+use crate::value::ValueType;
+
+/// Produces the symbol name used to look up an overload of `name` by its
+/// first argument's type, e.g. `delay` + `I16` -> `delay_i16`.
+pub fn mangling(name: &str, ty: ValueType) -> String {
+    let suffix = match ty {
+        ValueType::Void => "void",
+        ValueType::Bool => "bool",
+        ValueType::I8 => "i8",
+        ValueType::I16 => "i16",
+        ValueType::F32 => "f32",
+        ValueType::Str => "str",
+        ValueType::Array { .. } => panic!("cannot mangle an overload on an array argument"),
+        ValueType::Complex => "complex",
+    };
+    format!("{}_{}", name, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::ElementType;
+
+    #[test]
+    fn mangles_each_scalar_type() {
+        assert_eq!(mangling("delay", ValueType::Void), "delay_void");
+        assert_eq!(mangling("delay", ValueType::Bool), "delay_bool");
+        assert_eq!(mangling("delay", ValueType::I8), "delay_i8");
+        assert_eq!(mangling("delay", ValueType::I16), "delay_i16");
+        assert_eq!(mangling("delay", ValueType::F32), "delay_f32");
+        assert_eq!(mangling("delay", ValueType::Str), "delay_str");
+        assert_eq!(mangling("delay", ValueType::Complex), "delay_complex");
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot mangle an overload on an array argument")]
+    fn panics_on_array_argument() {
+        mangling(
+            "delay",
+            ValueType::Array {
+                element: ElementType::I16,
+                length: 4,
+            },
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use inkwell::types::StructType;
+use inkwell::values::{FloatValue, StructValue};
+use rustpython_parser::ast;
+
+use crate::compiler::error::{CompilerErrorReport, CompilerErrorType};
+use crate::compiler::Compiler;
+use crate::value::Value;
+
+impl<'a, 'ctx> Compiler<'a, 'ctx> {
+    /// `pub(crate)` so `irgen::stmt` can build the `{ f32, f32 }` LLVM type
+    /// for a `complex`-annotated function parameter or return type.
+    pub(crate) fn complex_type(&self) -> StructType<'ctx> {
+        let f32_type = self.context.f32_type();
+        self.context
+            .struct_type(&[f32_type.into(), f32_type.into()], false)
+    }
+
+    fn pack_complex(&self, re: FloatValue<'ctx>, im: FloatValue<'ctx>) -> StructValue<'ctx> {
+        let undef = self.complex_type().get_undef();
+        let with_re = self
+            .builder
+            .build_insert_value(undef, re, 0, "cplx_re")
+            .unwrap();
+        let with_im = self
+            .builder
+            .build_insert_value(with_re, im, 1, "cplx_im")
+            .unwrap();
+        with_im.into_struct_value()
+    }
+
+    fn unpack_complex(&self, value: StructValue<'ctx>) -> (FloatValue<'ctx>, FloatValue<'ctx>) {
+        let re = self
+            .builder
+            .build_extract_value(value, 0, "re")
+            .unwrap()
+            .into_float_value();
+        let im = self
+            .builder
+            .build_extract_value(value, 1, "im")
+            .unwrap()
+            .into_float_value();
+        (re, im)
+    }
+
+    /// Coerces any numeric `Value` to a `(re, im)` pair, treating reals as
+    /// `(x, 0)` so e.g. a phasor can be multiplied by a plain float gain.
+    fn complex_parts(&mut self, value: Value<'ctx>) -> (FloatValue<'ctx>, FloatValue<'ctx>) {
+        let zero = self.context.f32_type().const_float(0.0);
+        match value {
+            Value::Complex { value } => self.unpack_complex(value),
+            Value::F32 { value } => (value, zero),
+            Value::I8 { value } | Value::I16 { value } => {
+                let re =
+                    self.builder
+                        .build_signed_int_to_float(value, self.context.f32_type(), "toref");
+                (re, zero)
+            }
+            Value::Bool { value } => {
+                let re =
+                    self.builder
+                        .build_unsigned_int_to_float(value, self.context.f32_type(), "toref");
+                (re, zero)
+            }
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "cannot use a non-numeric value as a complex operand".to_string()
+            ))),
+        }
+    }
+
+    pub fn compile_complex_literal(&mut self, real: f64, imag: f64) -> Value<'ctx> {
+        let re = self.context.f32_type().const_float(real);
+        let im = self.context.f32_type().const_float(imag);
+        Value::Complex {
+            value: self.pack_complex(re, im),
+        }
+    }
+
+    pub fn negate_complex(&mut self, value: StructValue<'ctx>) -> Value<'ctx> {
+        let (re, im) = self.unpack_complex(value);
+        let re = self.builder.build_float_neg(re, "re");
+        let im = self.builder.build_float_neg(im, "im");
+        Value::Complex {
+            value: self.pack_complex(re, im),
+        }
+    }
+
+    /// Implements `+`, `-`, `*` and `/` via the standard real/imaginary
+    /// formulas, e.g. `(a+bi)(c+di) = (ac-bd) + (ad+bc)i`.
+    pub(crate) fn compile_complex_op(
+        &mut self,
+        a: Value<'ctx>,
+        op: &ast::Operator,
+        b: Value<'ctx>,
+    ) -> Value<'ctx> {
+        use ast::Operator::*;
+
+        let (a_re, a_im) = self.complex_parts(a);
+        let (b_re, b_im) = self.complex_parts(b);
+
+        let (re, im) = match op {
+            Add => (
+                self.builder.build_float_add(a_re, b_re, "re"),
+                self.builder.build_float_add(a_im, b_im, "im"),
+            ),
+            Sub => (
+                self.builder.build_float_sub(a_re, b_re, "re"),
+                self.builder.build_float_sub(a_im, b_im, "im"),
+            ),
+            Mult => {
+                let ac = self.builder.build_float_mul(a_re, b_re, "ac");
+                let bd = self.builder.build_float_mul(a_im, b_im, "bd");
+                let ad = self.builder.build_float_mul(a_re, b_im, "ad");
+                let bc = self.builder.build_float_mul(a_im, b_re, "bc");
+                (
+                    self.builder.build_float_sub(ac, bd, "re"),
+                    self.builder.build_float_add(ad, bc, "im"),
+                )
+            }
+            Div => {
+                // (a+bi)/(c+di) = ((ac+bd) + (bc-ad)i) / (c^2+d^2)
+                let ac = self.builder.build_float_mul(a_re, b_re, "ac");
+                let bd = self.builder.build_float_mul(a_im, b_im, "bd");
+                let bc = self.builder.build_float_mul(a_im, b_re, "bc");
+                let ad = self.builder.build_float_mul(a_re, b_im, "ad");
+                let cc = self.builder.build_float_mul(b_re, b_re, "cc");
+                let dd = self.builder.build_float_mul(b_im, b_im, "dd");
+                let denom = self.builder.build_float_add(cc, dd, "denom");
+                let num_re = self.builder.build_float_add(ac, bd, "num_re");
+                let num_im = self.builder.build_float_sub(bc, ad, "num_im");
+                (
+                    self.builder.build_float_div(num_re, denom, "re"),
+                    self.builder.build_float_div(num_im, denom, "im"),
+                )
+            }
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "operator is not supported for complex operands".to_string()
+            ))),
+        };
+
+        Value::Complex {
+            value: self.pack_complex(re, im),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::irgen::test_support::compiler_at_entry;
+    use inkwell::context::Context;
+
+    fn complex<'ctx>(compiler: &mut Compiler<'_, 'ctx>, re: f64, im: f64) -> Value<'ctx> {
+        compiler.compile_complex_literal(re, im)
+    }
+
+    #[test]
+    fn negate_complex_round_trips_through_pack_and_unpack() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let value = match complex(&mut compiler, 1.0, 2.0) {
+            Value::Complex { value } => value,
+            _ => unreachable!(),
+        };
+        assert!(matches!(compiler.negate_complex(value), Value::Complex { .. }));
+    }
+
+    #[test]
+    fn compile_complex_op_computes_mult_of_two_complex_operands() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = complex(&mut compiler, 1.0, 2.0);
+        let b = complex(&mut compiler, 3.0, 4.0);
+        let result = compiler.compile_complex_op(a, &ast::Operator::Mult, b);
+        assert!(matches!(result, Value::Complex { .. }));
+    }
+
+    #[test]
+    fn compile_complex_op_computes_div_of_two_complex_operands() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = complex(&mut compiler, 1.0, 2.0);
+        let b = complex(&mut compiler, 3.0, 4.0);
+        let result = compiler.compile_complex_op(a, &ast::Operator::Div, b);
+        assert!(matches!(result, Value::Complex { .. }));
+    }
+
+    #[test]
+    fn compile_complex_op_treats_a_real_operand_as_zero_imaginary() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = complex(&mut compiler, 1.0, 2.0);
+        let b = Value::F32 {
+            value: context.f32_type().const_float(5.0),
+        };
+        let result = compiler.compile_complex_op(a, &ast::Operator::Add, b);
+        assert!(matches!(result, Value::Complex { .. }));
+    }
+
+    #[test]
+    #[should_panic(expected = "TypeError")]
+    fn compile_complex_op_rejects_unsupported_operator() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = complex(&mut compiler, 1.0, 2.0);
+        let b = complex(&mut compiler, 3.0, 4.0);
+        compiler.compile_complex_op(a, &ast::Operator::FloorDiv, b);
+    }
+
+    #[test]
+    #[should_panic(expected = "TypeError")]
+    fn compile_complex_op_rejects_a_non_numeric_operand() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = complex(&mut compiler, 1.0, 2.0);
+        let b = Value::Str {
+            value: builder
+                .build_global_string_ptr("x", ".str")
+                .as_pointer_value(),
+        };
+        compiler.compile_complex_op(a, &ast::Operator::Add, b);
+    }
+}
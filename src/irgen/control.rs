@@ -0,0 +1,212 @@
+use inkwell::values::IntValue;
+use inkwell::IntPredicate;
+
+use crate::compiler::error::{CompilerErrorReport, CompilerErrorType};
+use crate::compiler::Compiler;
+use crate::value::{Value, ValueType};
+
+impl<'a, 'ctx> Compiler<'a, 'ctx> {
+    /// Lowers a condition to the `i1` branch-compatible value LLVM expects,
+    /// by comparing it against zero.
+    pub(crate) fn to_i1(&mut self, cond: Value<'ctx>) -> IntValue<'ctx> {
+        match cond {
+            Value::Bool { value } => value,
+            Value::I8 { value } | Value::I16 { value } => self.builder.build_int_compare(
+                IntPredicate::NE,
+                value,
+                value.get_type().const_zero(),
+                "truthy",
+            ),
+            Value::F32 { value } => self.builder.build_float_compare(
+                inkwell::FloatPredicate::ONE,
+                value,
+                value.get_type().const_zero(),
+                "truthy",
+            ),
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "condition must be a numeric or boolean value".to_string()
+            ))),
+        }
+    }
+
+    /// Shared if/else IR builder used by both the statement-level `if` and
+    /// the `test if body else orelse` ternary expression. Emits `then`,
+    /// `else` and `merge` blocks, compiles each branch via the given
+    /// closures, and — if both branches reach the merge block with a
+    /// non-`Void` value — unifies the results with a `phi` node. When only
+    /// one branch reaches it, that branch's value is returned as is.
+    ///
+    /// Returns `None` only when both branches terminate their own block
+    /// (e.g. both arms `return`), in which case the merge block is
+    /// unreachable and there is no value to produce.
+    pub(crate) fn gen_if<F1, F2>(
+        &mut self,
+        cond: Value<'ctx>,
+        then_fn: F1,
+        else_fn: F2,
+    ) -> Option<Value<'ctx>>
+    where
+        F1: FnOnce(&mut Self) -> Value<'ctx>,
+        F2: FnOnce(&mut Self) -> Value<'ctx>,
+    {
+        let cond = self.to_i1(cond);
+
+        let function = self.fn_value();
+        let then_bb = self.context.append_basic_block(function, "then");
+        let else_bb = self.context.append_basic_block(function, "else");
+        let merge_bb = self.context.append_basic_block(function, "merge");
+
+        self.builder
+            .build_conditional_branch(cond, then_bb, else_bb);
+
+        self.builder.position_at_end(then_bb);
+        let then_val = then_fn(self);
+        let then_end_bb = self.builder.get_insert_block().unwrap();
+        let then_terminated = then_end_bb.get_terminator().is_some();
+        if !then_terminated {
+            self.builder.build_unconditional_branch(merge_bb);
+        }
+
+        self.builder.position_at_end(else_bb);
+        let else_val = else_fn(self);
+        let else_end_bb = self.builder.get_insert_block().unwrap();
+        let else_terminated = else_end_bb.get_terminator().is_some();
+        if !else_terminated {
+            self.builder.build_unconditional_branch(merge_bb);
+        }
+
+        self.builder.position_at_end(merge_bb);
+
+        if then_terminated && else_terminated {
+            return None;
+        }
+
+        if then_val.get_type() != else_val.get_type() {
+            panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "if/else branches must produce the same type".to_string()
+            )));
+        }
+
+        if then_terminated {
+            return Some(else_val);
+        }
+        if else_terminated {
+            return Some(then_val);
+        }
+
+        // `Value::Void` has no basic-value representation, so it can't be
+        // merged through a phi node; both branches already agree on the
+        // type at this point, so just hand back a single `Value::Void`.
+        if then_val.get_type() == ValueType::Void {
+            return Some(Value::Void);
+        }
+
+        let then_bv = then_val.as_basic_value();
+        let else_bv = else_val.as_basic_value();
+        let phi = self.builder.build_phi(then_bv.get_type(), "ifphi");
+        phi.add_incoming(&[(&then_bv, then_end_bb), (&else_bv, else_end_bb)]);
+
+        Some(Value::from_basic_value(
+            then_val.get_type(),
+            phi.as_basic_value(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::irgen::test_support::compiler_at_entry;
+    use inkwell::context::Context;
+
+    #[test]
+    fn to_i1_passes_bool_through_unchanged() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let cond = Value::Bool {
+            value: context.bool_type().const_int(1, false),
+        };
+        let i1 = compiler.to_i1(cond);
+        assert_eq!(i1.get_type(), context.bool_type());
+    }
+
+    #[test]
+    fn to_i1_compares_integers_against_zero() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let cond = Value::I16 {
+            value: context.i16_type().const_int(5, true),
+        };
+        let i1 = compiler.to_i1(cond);
+        assert_eq!(i1.get_type(), context.bool_type());
+    }
+
+    #[test]
+    fn gen_if_merges_matching_branches_via_phi() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let cond = Value::Bool {
+            value: context.bool_type().const_int(1, false),
+        };
+        let result = compiler.gen_if(
+            cond,
+            |c| Value::I16 {
+                value: c.context.i16_type().const_int(1, true),
+            },
+            |c| Value::I16 {
+                value: c.context.i16_type().const_int(2, true),
+            },
+        );
+        assert!(matches!(result, Some(Value::I16 { .. })));
+    }
+
+    /// Regression test for a bug where both branches yielding `Value::Void`
+    /// (e.g. an `if` with no value-producing `else`) tried to merge through
+    /// a `phi` node, which panics because `Void` has no basic-value form.
+    #[test]
+    fn gen_if_merges_void_branches_without_building_a_phi() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let cond = Value::Bool {
+            value: context.bool_type().const_int(1, false),
+        };
+        let result = compiler.gen_if(cond, |_| Value::Void, |_| Value::Void);
+        assert!(matches!(result, Some(Value::Void)));
+    }
+
+    #[test]
+    fn gen_if_returns_none_when_both_branches_terminate() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let cond = Value::Bool {
+            value: context.bool_type().const_int(1, false),
+        };
+        let result = compiler.gen_if(
+            cond,
+            |c| {
+                c.builder.build_return(None);
+                Value::Void
+            },
+            |c| {
+                c.builder.build_return(None);
+                Value::Void
+            },
+        );
+        assert!(result.is_none());
+    }
+}
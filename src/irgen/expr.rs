@@ -1,5 +1,7 @@
 use either::Either;
-use inkwell::values::BasicValueEnum;
+use inkwell::values::{BasicValueEnum, IntValue, PointerValue};
+use inkwell::{FloatPredicate, IntPredicate};
+use num_traits::ToPrimitive;
 use rustpython_parser::ast;
 
 use crate::compiler::error::{CompilerErrorReport, CompilerErrorType};
@@ -14,6 +16,7 @@ pub trait CGExpr<'a, 'ctx> {
         &mut self,
         func: &Box<ast::Expression>,
         args: &Vec<ast::Expression>,
+        keywords: &Vec<ast::Keyword>,
     ) -> Value<'ctx>;
 }
 
@@ -33,12 +36,7 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
                 ast::Number::Float { value } => Value::F32 {
                     value: self.context.f32_type().const_float(*value),
                 },
-                ast::Number::Complex { real: _, imag: _ } => {
-                    panic!(
-                        "{:?}\nNotImplemented builder for imaginary number",
-                        self.current_source_location
-                    );
-                }
+                ast::Number::Complex { real, imag } => self.compile_complex_literal(*real, *imag),
             },
             ExpressionType::String { value } => {
                 let v = try_get_constant_string(value).unwrap();
@@ -58,10 +56,7 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
                 function,
                 args,
                 keywords,
-            } => {
-                let _keywords = keywords;
-                self.compile_expr_call(function, args)
-            }
+            } => self.compile_expr_call(function, args, keywords),
             ExpressionType::Binop { a, op, b } => {
                 let a = self.compile_expr(a);
                 let b = self.compile_expr(b);
@@ -71,37 +66,45 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
                 if self.fn_value_opt.is_none() {
                     let (ty, pointer) = match self.variables.get(name) {
                         Some(tuple) => tuple,
-                        None => panic!(self.errs(CompilerErrorType::NameError(name))),
+                        None => panic!("{}", self.errs(CompilerErrorType::NameError(name))),
                     };
-                    match *pointer {
-                        var => {
-                            Value::from_basic_value(*ty, self.builder.build_load(var, name).into())
-                        }
-                    }
+                    self.load_variable(ty, *pointer, name)
                 } else {
                     let getter = self.fn_scope.get(&self.fn_value()).unwrap().get(name);
                     if getter.is_none() {
                         let (ty, pointer) = match self.variables.get(name) {
                             Some(tuple) => tuple,
-                            None => panic!(self.errs(CompilerErrorType::NameError(name))),
+                            None => panic!("{}", self.errs(CompilerErrorType::NameError(name))),
                         };
-                        match *pointer {
-                            var => Value::from_basic_value(
-                                *ty,
-                                self.builder.build_load(var, name).into(),
-                            ),
-                        }
+                        self.load_variable(ty, *pointer, name)
                     } else {
                         let (ty, pointer) = getter.unwrap();
-                        match *pointer {
-                            var => Value::from_basic_value(
-                                *ty,
-                                self.builder.build_load(var, name).into(),
-                            ),
-                        }
+                        self.load_variable(ty, *pointer, name)
                     }
                 }
             }
+            ExpressionType::Subscript { a, b } => {
+                let base = self.compile_expr(a);
+                let (array_ptr, element, length) = match base {
+                    Value::Array {
+                        value,
+                        element,
+                        length,
+                    } => (value, element, length),
+                    _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                        "subscript target is not an array".to_string()
+                    ))),
+                };
+
+                let index = self.compile_index(b, length);
+
+                let element_ptr = unsafe {
+                    self.builder
+                        .build_in_bounds_gep(array_ptr, &[index], "elemptr")
+                };
+                let loaded = self.builder.build_load(element_ptr, "elem");
+                Value::from_basic_value(element.as_value_type(), loaded)
+            }
             ExpressionType::Compare { vals, ops } => self.compile_comparison(vals, ops),
             ExpressionType::None => Value::Void,
             ExpressionType::True => Value::Bool {
@@ -110,32 +113,15 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
             ExpressionType::False => Value::Bool {
                 value: self.context.bool_type().const_int(0, false),
             },
-            ExpressionType::Unop { op, a } => match &a.node {
-                ExpressionType::Number { value } => match value {
-                    ast::Number::Integer { value } => match op {
-                        ast::UnaryOperator::Neg => Value::I16 {
-                            value: self
-                                .context
-                                .i16_type()
-                                .const_int(truncate_bigint_to_u64(&-value), true),
-                        },
-                        _ => panic!("NotImplemented unop for i16"),
-                    },
-                    ast::Number::Float { value } => match op {
-                        ast::UnaryOperator::Neg => Value::F32 {
-                            value: self.context.f32_type().const_float(-value.clone()),
-                        },
-                        _ => panic!("NotImplemented unop for f32"),
-                    },
-                    ast::Number::Complex { real: _, imag: _ } => {
-                        panic!(
-                            "{:?}\nNotImplemented builder for imaginary number",
-                            self.current_source_location
-                        );
-                    }
-                },
-                _ => panic!("NotImplemented type for unop"),
-            },
+            ExpressionType::Unop { op, a } => {
+                let a = self.compile_expr(a);
+                self.compile_unop(op, a)
+            }
+            ExpressionType::IfExpression { test, body, orelse } => {
+                let cond = self.compile_expr(test);
+                self.gen_if(cond, |c| c.compile_expr(body), |c| c.compile_expr(orelse))
+                    .unwrap_or(Value::Void)
+            }
             ExpressionType::Ellipsis => panic!("Constant value Ellipsis is not implemented."),
             _ => {
                 panic!(
@@ -150,6 +136,7 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
         &mut self,
         func: &Box<ast::Expression>,
         args: &Vec<ast::Expression>,
+        keywords: &Vec<ast::Keyword>,
     ) -> Value<'ctx> {
         let func_name = match &func.node {
             ast::ExpressionType::Identifier { name } => name,
@@ -162,12 +149,26 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
         }
         .to_string();
 
-        let first_arg = self.compile_expr(args.clone().first().unwrap());
+        // Overload resolution mangles on the first argument's type,
+        // whether it was passed positionally or by keyword; compile just
+        // that one argument up front so it's only evaluated once.
+        let probe = if let Some(first) = args.first() {
+            Some(self.compile_expr(first))
+        } else {
+            keywords.first().map(|kw| self.compile_expr(&kw.value))
+        };
 
         let func = match self.get_function(func_name.as_ref()) {
             Some(f) => f,
             None => {
-                let func_name_mangled = mangling(&func_name, first_arg.get_type());
+                let probe_value = probe.expect(
+                    format!(
+                        "{:?}\nFunction '{}' is not defined",
+                        self.current_source_location, func_name
+                    )
+                    .as_str(),
+                );
+                let func_name_mangled = mangling(&func_name, probe_value.get_type());
                 self.get_function(func_name_mangled.as_ref()).expect(
                     format!(
                         "{:?}\nFunction '{}' is not defined",
@@ -179,17 +180,72 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
         };
 
         let args_proto = func.get_params();
+        let param_names = self.get_param_names(func);
 
-        let mut args_value: Vec<BasicValueEnum> = vec![];
+        // Place positional arguments first, then slot each keyword
+        // argument into its declared position by name.
+        let mut slots: Vec<Option<Value<'ctx>>> = vec![None; args_proto.len()];
 
-        for (i, expr_proto) in args.iter().zip(args_proto.iter()).enumerate() {
-            let expr = expr_proto.0;
-            let proto = expr_proto.1;
-            let value = if i == 0 {
-                first_arg
+        for (i, expr) in args.iter().enumerate() {
+            if i >= slots.len() {
+                panic!("{}", self.errs(CompilerErrorType::ArgumentError(format!(
+                    "'{}' takes {} argument(s) but {} were given",
+                    func_name,
+                    slots.len(),
+                    args.len()
+                ))));
+            }
+            slots[i] = Some(if i == 0 {
+                probe.unwrap()
             } else {
                 self.compile_expr(expr)
-            };
+            });
+        }
+
+        for (k, kw) in keywords.iter().enumerate() {
+            let name = kw.name.as_ref().unwrap_or_else(|| {
+                panic!("{}", self.errs(CompilerErrorType::ArgumentError(format!(
+                    "'**' argument unpacking is not supported in call to '{}'",
+                    func_name
+                ))))
+            });
+            let index = param_names
+                .as_ref()
+                .and_then(|names| names.iter().position(|p| p == name))
+                .unwrap_or_else(|| {
+                    panic!("{}", self.errs(CompilerErrorType::ArgumentError(format!(
+                        "'{}' got an unexpected keyword argument '{}'",
+                        func_name, name
+                    ))))
+                });
+            if slots[index].is_some() {
+                panic!("{}", self.errs(CompilerErrorType::ArgumentError(format!(
+                    "'{}' got multiple values for argument '{}'",
+                    func_name, name
+                ))));
+            }
+            let is_probe = args.is_empty() && k == 0;
+            slots[index] = Some(if is_probe {
+                probe.unwrap()
+            } else {
+                self.compile_expr(&kw.value)
+            });
+        }
+
+        let mut args_value: Vec<BasicValueEnum> = vec![];
+
+        for (i, (slot, proto)) in slots.into_iter().zip(args_proto.iter()).enumerate() {
+            let value = slot.unwrap_or_else(|| {
+                let name = param_names
+                    .as_ref()
+                    .and_then(|names| names.get(i))
+                    .cloned()
+                    .unwrap_or_else(|| i.to_string());
+                panic!("{}", self.errs(CompilerErrorType::ArgumentError(format!(
+                    "'{}' is missing required argument '{}'",
+                    func_name, name
+                ))))
+            });
             match value {
                 Value::I8 { value } => {
                     let cast = self.builder.build_int_cast(
@@ -209,6 +265,8 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
                 }
                 Value::F32 { value } => args_value.push(BasicValueEnum::FloatValue(value)),
                 Value::Str { value } => args_value.push(BasicValueEnum::PointerValue(value)),
+                Value::Array { value, .. } => args_value.push(BasicValueEnum::PointerValue(value)),
+                Value::Complex { value } => args_value.push(BasicValueEnum::StructValue(value)),
                 _ => panic!(
                     "{:?}\nNotImplemented argument type",
                     self.current_source_location
@@ -232,6 +290,8 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
                     }
                 } else if bv.is_float_value() {
                     ValueType::F32
+                } else if bv.is_struct_value() {
+                    ValueType::Complex
                 } else {
                     unreachable!()
                 },
@@ -241,3 +301,338 @@ impl<'a, 'ctx> CGExpr<'a, 'ctx> for Compiler<'a, 'ctx> {
         }
     }
 }
+
+impl<'a, 'ctx> Compiler<'a, 'ctx> {
+    /// Loads a named variable, except for arrays: those are kept as the
+    /// pointer to their first element rather than loaded as a scalar.
+    fn load_variable(
+        &self,
+        ty: &ValueType,
+        pointer: PointerValue<'ctx>,
+        name: &str,
+    ) -> Value<'ctx> {
+        match ty {
+            ValueType::Array { element, length } => Value::Array {
+                value: pointer,
+                element: *element,
+                length: *length,
+            },
+            _ => Value::from_basic_value(*ty, self.builder.build_load(pointer, name).into()),
+        }
+    }
+
+    /// Lowers an array index expression to an `i16`, applying Python's
+    /// negative-index wraparound. Constant indices are folded (and bounds
+    /// checked) at compile time; runtime indices get a branch that adds the
+    /// array length when the value is negative.
+    fn compile_index(&mut self, index: &ast::Expression, length: u32) -> IntValue<'ctx> {
+        let i16_ty = self.context.i16_type();
+
+        // A negative literal such as `-1` parses as unary minus applied to `1`.
+        let literal = match &index.node {
+            ast::ExpressionType::Number {
+                value: ast::Number::Integer { value },
+            } => Some(value.clone()),
+            ast::ExpressionType::Unop {
+                op: ast::UnaryOperator::Neg,
+                a,
+            } => match &a.node {
+                ast::ExpressionType::Number {
+                    value: ast::Number::Integer { value },
+                } => Some(-value.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(value) = literal {
+            let raw = value.to_i16().map(i64::from).unwrap_or_else(|| {
+                panic!("{}", self.errs(CompilerErrorType::IndexError(format!(
+                    "index {} does not fit in an i16",
+                    value
+                ))))
+            });
+            let resolved = if raw < 0 { raw + length as i64 } else { raw };
+            if resolved < 0 || resolved >= length as i64 {
+                panic!("{}", self.errs(CompilerErrorType::IndexError(format!(
+                    "index {} out of range for array of length {}",
+                    raw, length
+                ))));
+            }
+            return i16_ty.const_int(resolved as u64, true);
+        }
+
+        let index = self.compile_expr(index);
+        let index = match index {
+            Value::I16 { value } => value,
+            Value::I8 { value } => self.builder.build_int_cast(value, i16_ty, "idxext"),
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "array index must be an integer".to_string()
+            ))),
+        };
+
+        let is_negative =
+            self.builder
+                .build_int_compare(IntPredicate::SLT, index, i16_ty.const_zero(), "idxneg");
+
+        let function = self.fn_value();
+        let wrap_bb = self.context.append_basic_block(function, "idx_wrap");
+        let merge_bb = self.context.append_basic_block(function, "idx_merge");
+        let entry_bb = self.builder.get_insert_block().unwrap();
+
+        self.builder
+            .build_conditional_branch(is_negative, wrap_bb, merge_bb);
+
+        self.builder.position_at_end(wrap_bb);
+        let wrapped =
+            self.builder
+                .build_int_add(index, i16_ty.const_int(length as u64, false), "idxwrap");
+        self.builder.build_unconditional_branch(merge_bb);
+        let wrap_bb = self.builder.get_insert_block().unwrap();
+
+        self.builder.position_at_end(merge_bb);
+        let phi = self.builder.build_phi(i16_ty, "idxphi");
+        phi.add_incoming(&[(&wrapped, wrap_bb), (&index, entry_bb)]);
+        phi.as_basic_value().into_int_value()
+    }
+
+    /// Lowers a unary operator applied to an already-compiled operand.
+    /// Following CPython semantics, `Bool` is first promoted to `I16`
+    /// for every op except `Not`, which instead yields a fresh `Bool`.
+    fn compile_unop(&mut self, op: &ast::UnaryOperator, a: Value<'ctx>) -> Value<'ctx> {
+        use ast::UnaryOperator::*;
+
+        let a = match (op, a) {
+            (Neg | UAdd | Invert, Value::Bool { value }) => Value::I16 {
+                value: self
+                    .builder
+                    .build_int_z_extend(value, self.context.i16_type(), "boolext"),
+            },
+            _ => a,
+        };
+
+        match op {
+            Neg => match a {
+                Value::I8 { value } => Value::I8 {
+                    value: self.builder.build_int_neg(value, "neg"),
+                },
+                Value::I16 { value } => Value::I16 {
+                    value: self.builder.build_int_neg(value, "neg"),
+                },
+                Value::F32 { value } => Value::F32 {
+                    value: self.builder.build_float_neg(value, "neg"),
+                },
+                Value::Complex { value } => self.negate_complex(value),
+                _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                    "unary '-' requires a numeric operand".to_string()
+                ))),
+            },
+            UAdd => match a {
+                Value::I8 { .. }
+                | Value::I16 { .. }
+                | Value::F32 { .. }
+                | Value::Complex { .. } => a,
+                _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                    "unary '+' requires a numeric operand".to_string()
+                ))),
+            },
+            Invert => match a {
+                Value::I8 { value } => Value::I8 {
+                    value: self.builder.build_not(value, "inv"),
+                },
+                // Note: for a bool-promoted operand this computes `~x`,
+                // which is `-(x + 1)` in two's complement, matching Python.
+                Value::I16 { value } => Value::I16 {
+                    value: self.builder.build_not(value, "inv"),
+                },
+                _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                    "unary '~' requires an integer operand".to_string()
+                ))),
+            },
+            Not => {
+                let value = match a {
+                    Value::Bool { value } | Value::I8 { value } | Value::I16 { value } => {
+                        self.builder.build_int_compare(
+                            IntPredicate::EQ,
+                            value,
+                            value.get_type().const_zero(),
+                            "not",
+                        )
+                    }
+                    Value::F32 { value } => self.builder.build_float_compare(
+                        FloatPredicate::OEQ,
+                        value,
+                        value.get_type().const_zero(),
+                        "not",
+                    ),
+                    _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                        "'not' requires a numeric operand".to_string()
+                    ))),
+                };
+                Value::Bool { value }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use inkwell::context::Context;
+    use num_bigint::BigInt;
+
+    fn const_index(n: i64) -> ast::Expression {
+        ast::Expression {
+            location: ast::Location::new(1, 1),
+            node: ast::ExpressionType::Number {
+                value: ast::Number::Integer {
+                    value: BigInt::from(n),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn folds_negative_constant_index() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = Compiler::new("test".to_string(), &context, &builder, &module);
+
+        let index = compiler.compile_index(&const_index(-1), 4);
+        assert_eq!(index.get_zero_extended_constant(), Some(3));
+    }
+
+    #[test]
+    fn keeps_in_range_constant_index_unchanged() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = Compiler::new("test".to_string(), &context, &builder, &module);
+
+        let index = compiler.compile_index(&const_index(2), 4);
+        assert_eq!(index.get_zero_extended_constant(), Some(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "IndexError")]
+    fn rejects_out_of_range_constant_index() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = Compiler::new("test".to_string(), &context, &builder, &module);
+
+        compiler.compile_index(&const_index(10), 4);
+    }
+
+    fn identifier(name: &str) -> ast::Expression {
+        ast::Expression {
+            location: ast::Location::new(1, 1),
+            node: ast::ExpressionType::Identifier {
+                name: name.to_string(),
+            },
+        }
+    }
+
+    fn keyword(name: &str, value: ast::Expression) -> ast::Keyword {
+        ast::Keyword {
+            name: Some(name.to_string()),
+            value,
+        }
+    }
+
+    fn call(func: &str, args: Vec<ast::Expression>, keywords: Vec<ast::Keyword>) -> ast::Expression {
+        ast::Expression {
+            location: ast::Location::new(1, 1),
+            node: ast::ExpressionType::Call {
+                function: Box::new(identifier(func)),
+                args,
+                keywords,
+            },
+        }
+    }
+
+    /// Unlike `irgen::test_support::compiler_at_entry`, keyword-argument
+    /// resolution needs an actual registered callee to resolve names
+    /// against, so this builds and positions its own two-parameter
+    /// function rather than the shared fixture's empty one.
+    fn compiler_with_two_arg_fn<'ctx>(
+        context: &'ctx Context,
+        builder: &'ctx inkwell::builder::Builder<'ctx>,
+        module: &'ctx inkwell::module::Module<'ctx>,
+    ) -> Compiler<'ctx, 'ctx> {
+        let mut compiler = Compiler::new("test".to_string(), context, builder, module);
+
+        let i16_ty = context.i16_type();
+        let fn_type = context
+            .void_type()
+            .fn_type(&[i16_ty.into(), i16_ty.into()], false);
+        let function = module.add_function("two_arg", fn_type, None);
+        compiler.register_function(
+            "two_arg".to_string(),
+            function,
+            vec!["a".to_string(), "b".to_string()],
+        );
+
+        let entry = context.append_basic_block(function, "entry");
+        builder.position_at_end(entry);
+        compiler.fn_value_opt = Some(function);
+        compiler
+    }
+
+    /// Regression test for a bug where keyword-argument slots resolved
+    /// against an empty parameter-name list (nothing was ever threaded into
+    /// `register_function`), so every keyword call failed to find its name.
+    #[test]
+    fn keyword_argument_resolves_to_its_declared_position_out_of_order() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_with_two_arg_fn(&context, &builder, &module);
+
+        let expr = call(
+            "two_arg",
+            vec![],
+            vec![
+                keyword("b", const_index(2)),
+                keyword("a", const_index(1)),
+            ],
+        );
+
+        assert!(matches!(compiler.compile_expr(&expr), Value::Void));
+    }
+
+    #[test]
+    #[should_panic(expected = "ArgumentError")]
+    fn keyword_argument_rejects_unknown_name() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_with_two_arg_fn(&context, &builder, &module);
+
+        let expr = call(
+            "two_arg",
+            vec![const_index(1)],
+            vec![keyword("c", const_index(2))],
+        );
+
+        compiler.compile_expr(&expr);
+    }
+
+    #[test]
+    #[should_panic(expected = "ArgumentError")]
+    fn keyword_argument_rejects_a_name_already_given_positionally() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_with_two_arg_fn(&context, &builder, &module);
+
+        let expr = call(
+            "two_arg",
+            vec![const_index(1)],
+            vec![keyword("a", const_index(2))],
+        );
+
+        compiler.compile_expr(&expr);
+    }
+}
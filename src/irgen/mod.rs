@@ -0,0 +1,7 @@
+pub mod complex;
+pub mod control;
+pub mod expr;
+pub mod ops;
+pub mod stmt;
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,490 @@
+use inkwell::values::{BasicValueEnum, FloatValue, IntValue};
+use inkwell::{FloatPredicate, IntPredicate};
+use rustpython_parser::ast;
+
+use crate::compiler::error::{CompilerErrorReport, CompilerErrorType};
+use crate::compiler::Compiler;
+use crate::value::{Value, ValueType};
+
+impl<'a, 'ctx> Compiler<'a, 'ctx> {
+    /// Picks the "wider" of two operand types following the fixed
+    /// precedence `Bool < I8 < I16 < F32` and casts both operands to it,
+    /// so binary ops and comparisons can operate on a single unified type.
+    fn promote(&mut self, a: Value<'ctx>, b: Value<'ctx>) -> (Value<'ctx>, Value<'ctx>, ValueType) {
+        fn rank(ty: ValueType) -> u8 {
+            match ty {
+                ValueType::Bool => 0,
+                ValueType::I8 => 1,
+                ValueType::I16 => 2,
+                ValueType::F32 => 3,
+                _ => 4,
+            }
+        }
+
+        let ty = if rank(a.get_type()) >= rank(b.get_type()) {
+            a.get_type()
+        } else {
+            b.get_type()
+        };
+
+        (self.cast_to(a, ty), self.cast_to(b, ty), ty)
+    }
+
+    fn cast_to(&mut self, value: Value<'ctx>, ty: ValueType) -> Value<'ctx> {
+        if value.get_type() == ty {
+            return value;
+        }
+
+        match (value, ty) {
+            (Value::Bool { value }, ValueType::I8) => Value::I8 {
+                value: self
+                    .builder
+                    .build_int_z_extend(value, self.context.i8_type(), "boolext"),
+            },
+            (Value::Bool { value }, ValueType::I16) => Value::I16 {
+                value: self
+                    .builder
+                    .build_int_z_extend(value, self.context.i16_type(), "boolext"),
+            },
+            (Value::Bool { value }, ValueType::F32) => Value::F32 {
+                value: self.builder.build_unsigned_int_to_float(
+                    value,
+                    self.context.f32_type(),
+                    "boolf",
+                ),
+            },
+            (Value::I8 { value }, ValueType::I16) => Value::I16 {
+                value: self
+                    .builder
+                    .build_int_s_extend(value, self.context.i16_type(), "widen"),
+            },
+            (Value::I8 { value }, ValueType::F32) => Value::F32 {
+                value: self.builder.build_signed_int_to_float(
+                    value,
+                    self.context.f32_type(),
+                    "tof",
+                ),
+            },
+            (Value::I16 { value }, ValueType::F32) => Value::F32 {
+                value: self.builder.build_signed_int_to_float(
+                    value,
+                    self.context.f32_type(),
+                    "tof",
+                ),
+            },
+            (value, ty) => panic!("{}", self.errs(CompilerErrorType::TypeError(format!(
+                "cannot unify {:?} with {:?}",
+                value.get_type(),
+                ty
+            )))),
+        }
+    }
+
+    /// Computes Python's floor-division quotient and modulo remainder
+    /// together, since both need the same correction: LLVM's `sdiv`/`srem`
+    /// truncate toward zero and give the remainder the dividend's sign (C
+    /// semantics), so whenever the truncated remainder is nonzero and
+    /// disagrees in sign with the divisor, the quotient is one too high and
+    /// the remainder is `b` short of Python's floor semantics
+    /// (`-7 // 2 == -4`, `-7 % 2 == 1`). Applied branch-free: the mismatch
+    /// test is zero-extended to `0`/`1` and folded straight into the
+    /// quotient/remainder arithmetic rather than guarded by a conditional.
+    fn int_floor_div_rem(
+        &mut self,
+        a: IntValue<'ctx>,
+        b: IntValue<'ctx>,
+    ) -> (IntValue<'ctx>, IntValue<'ctx>) {
+        let int_ty = a.get_type();
+        let trunc_q = self.builder.build_int_signed_div(a, b, "div");
+        let trunc_r = self.builder.build_int_signed_rem(a, b, "rem");
+
+        let r_nonzero = self.builder.build_int_compare(
+            IntPredicate::NE,
+            trunc_r,
+            int_ty.const_zero(),
+            "rnonzero",
+        );
+        let r_neg =
+            self.builder
+                .build_int_compare(IntPredicate::SLT, trunc_r, int_ty.const_zero(), "rneg");
+        let b_neg =
+            self.builder
+                .build_int_compare(IntPredicate::SLT, b, int_ty.const_zero(), "bneg");
+        let signs_differ = self.builder.build_xor(r_neg, b_neg, "signsdiffer");
+        let needs_adjust = self.builder.build_and(r_nonzero, signs_differ, "needsadjust");
+        let adjust = self
+            .builder
+            .build_int_z_extend(needs_adjust, int_ty, "adjust");
+
+        let q = self.builder.build_int_sub(trunc_q, adjust, "flooredq");
+        let adjust_by_b = self.builder.build_int_mul(adjust, b, "adjustbyb");
+        let r = self.builder.build_int_add(trunc_r, adjust_by_b, "flooredr");
+
+        (q, r)
+    }
+
+    /// Float counterpart of `int_floor_div_rem`: `build_float_rem` is `fmod`,
+    /// which like `srem` takes the dividend's sign, and since
+    /// `build_float_div` is plain division (not truncated), the truncated
+    /// quotient is taken as `(a - fmod(a, b)) / b`. The same sign-mismatch
+    /// correction then applies (`-7.5 // 2.0 == -4.0`, `-7.5 % 2.0 == 0.5`).
+    fn float_floor_div_rem(
+        &mut self,
+        a: FloatValue<'ctx>,
+        b: FloatValue<'ctx>,
+    ) -> (FloatValue<'ctx>, FloatValue<'ctx>) {
+        let float_ty = a.get_type();
+        let trunc_r = self.builder.build_float_rem(a, b, "rem");
+        let a_minus_r = self.builder.build_float_sub(a, trunc_r, "aminusr");
+        let trunc_q = self.builder.build_float_div(a_minus_r, b, "div");
+
+        let r_nonzero = self.builder.build_float_compare(
+            FloatPredicate::ONE,
+            trunc_r,
+            float_ty.const_zero(),
+            "rnonzero",
+        );
+        let r_neg = self.builder.build_float_compare(
+            FloatPredicate::OLT,
+            trunc_r,
+            float_ty.const_zero(),
+            "rneg",
+        );
+        let b_neg =
+            self.builder
+                .build_float_compare(FloatPredicate::OLT, b, float_ty.const_zero(), "bneg");
+        let signs_differ = self.builder.build_xor(r_neg, b_neg, "signsdiffer");
+        let needs_adjust = self.builder.build_and(r_nonzero, signs_differ, "needsadjust");
+        let adjust = self
+            .builder
+            .build_unsigned_int_to_float(needs_adjust, float_ty, "adjust");
+
+        let q = self.builder.build_float_sub(trunc_q, adjust, "flooredq");
+        let adjust_by_b = self.builder.build_float_mul(adjust, b, "adjustbyb");
+        let r = self.builder.build_float_add(trunc_r, adjust_by_b, "flooredr");
+
+        (q, r)
+    }
+
+    pub fn compile_op(
+        &mut self,
+        a: Value<'ctx>,
+        op: &ast::Operator,
+        b: Value<'ctx>,
+    ) -> Value<'ctx> {
+        use ast::Operator::*;
+
+        if matches!(a, Value::Complex { .. }) || matches!(b, Value::Complex { .. }) {
+            return self.compile_complex_op(a, op, b);
+        }
+
+        // Python's `/` is true division and always yields a float, even for
+        // two ints (`5 / 2 == 2.5`); only `//` performs integer floor
+        // division, so `/` is promoted to `F32` ahead of the usual `promote`.
+        if matches!(op, Div) {
+            let a = self
+                .cast_to(a, ValueType::F32)
+                .as_basic_value()
+                .into_float_value();
+            let b = self
+                .cast_to(b, ValueType::F32)
+                .as_basic_value()
+                .into_float_value();
+            return Value::F32 {
+                value: self.builder.build_float_div(a, b, "div"),
+            };
+        }
+
+        let (a, b, ty) = self.promote(a, b);
+
+        match ty {
+            ValueType::F32 => {
+                let a = a.as_basic_value().into_float_value();
+                let b = b.as_basic_value().into_float_value();
+                let value = match op {
+                    Add => self.builder.build_float_add(a, b, "add"),
+                    Sub => self.builder.build_float_sub(a, b, "sub"),
+                    Mult => self.builder.build_float_mul(a, b, "mul"),
+                    FloorDiv => self.float_floor_div_rem(a, b).0,
+                    Mod => self.float_floor_div_rem(a, b).1,
+                    _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                        "operator is not supported for float operands".to_string()
+                    ))),
+                };
+                Value::F32 { value }
+            }
+            ValueType::Bool | ValueType::I8 | ValueType::I16 => {
+                let a = a.as_basic_value().into_int_value();
+                let b = b.as_basic_value().into_int_value();
+                let value = match op {
+                    Add => self.builder.build_int_add(a, b, "add"),
+                    Sub => self.builder.build_int_sub(a, b, "sub"),
+                    Mult => self.builder.build_int_mul(a, b, "mul"),
+                    FloorDiv => self.int_floor_div_rem(a, b).0,
+                    Mod => self.int_floor_div_rem(a, b).1,
+                    BitAnd => self.builder.build_and(a, b, "and"),
+                    BitOr => self.builder.build_or(a, b, "or"),
+                    BitXor => self.builder.build_xor(a, b, "xor"),
+                    LShift => self.builder.build_left_shift(a, b, "shl"),
+                    RShift => self.builder.build_right_shift(a, b, true, "shr"),
+                    _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                        "operator is not supported for integer operands".to_string()
+                    ))),
+                };
+                Value::from_basic_value(ty, BasicValueEnum::IntValue(value))
+            }
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "binary operators require numeric operands".to_string()
+            ))),
+        }
+    }
+
+    pub fn compile_comparison(
+        &mut self,
+        vals: &Vec<ast::Expression>,
+        ops: &Vec<ast::Comparison>,
+    ) -> Value<'ctx> {
+        let mut left = self.compile_expr(&vals[0]);
+        let mut result: Option<IntValue<'ctx>> = None;
+
+        for (op, rhs_expr) in ops.iter().zip(vals[1..].iter()) {
+            let right = self.compile_expr(rhs_expr);
+            let (a, b, ty) = self.promote(left, right);
+            let cmp = self.compile_single_comparison(a, op, b, ty);
+            result = Some(match result {
+                None => cmp,
+                Some(prev) => self.builder.build_and(prev, cmp, "cmpand"),
+            });
+            left = right;
+        }
+
+        Value::Bool {
+            value: result.expect("rustpython_parser guarantees at least one comparison operator"),
+        }
+    }
+
+    fn compile_single_comparison(
+        &mut self,
+        a: Value<'ctx>,
+        op: &ast::Comparison,
+        b: Value<'ctx>,
+        ty: ValueType,
+    ) -> IntValue<'ctx> {
+        use ast::Comparison::*;
+
+        match ty {
+            ValueType::F32 => {
+                let a = a.as_basic_value().into_float_value();
+                let b = b.as_basic_value().into_float_value();
+                let pred = match op {
+                    Equal => FloatPredicate::OEQ,
+                    NotEqual => FloatPredicate::ONE,
+                    Less => FloatPredicate::OLT,
+                    LessOrEqual => FloatPredicate::OLE,
+                    Greater => FloatPredicate::OGT,
+                    GreaterOrEqual => FloatPredicate::OGE,
+                    _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                        "comparison is not supported for float operands".to_string()
+                    ))),
+                };
+                self.builder.build_float_compare(pred, a, b, "cmp")
+            }
+            ValueType::Bool | ValueType::I8 | ValueType::I16 => {
+                let a = a.as_basic_value().into_int_value();
+                let b = b.as_basic_value().into_int_value();
+                let pred = match op {
+                    Equal => IntPredicate::EQ,
+                    NotEqual => IntPredicate::NE,
+                    Less => IntPredicate::SLT,
+                    LessOrEqual => IntPredicate::SLE,
+                    Greater => IntPredicate::SGT,
+                    GreaterOrEqual => IntPredicate::SGE,
+                    _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                        "comparison is not supported for integer operands".to_string()
+                    ))),
+                };
+                self.builder.build_int_compare(pred, a, b, "cmp")
+            }
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "comparisons require numeric operands".to_string()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::irgen::test_support::compiler_at_entry;
+    use inkwell::context::Context;
+
+    #[test]
+    fn promotes_bool_and_i16_to_i16() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = Value::Bool {
+            value: context.bool_type().const_int(1, false),
+        };
+        let b = Value::I16 {
+            value: context.i16_type().const_int(5, true),
+        };
+
+        let (a, b, ty) = compiler.promote(a, b);
+        assert_eq!(ty, ValueType::I16);
+        assert!(matches!(a, Value::I16 { .. }));
+        assert!(matches!(b, Value::I16 { .. }));
+    }
+
+    #[test]
+    fn promotes_i8_and_f32_to_f32() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = Value::I8 {
+            value: context.i8_type().const_int(3, true),
+        };
+        let b = Value::F32 {
+            value: context.f32_type().const_float(1.5),
+        };
+
+        let (a, b, ty) = compiler.promote(a, b);
+        assert_eq!(ty, ValueType::F32);
+        assert!(matches!(a, Value::F32 { .. }));
+        assert!(matches!(b, Value::F32 { .. }));
+    }
+
+    #[test]
+    fn widens_i8_to_i16_when_both_sides_are_integers() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = Value::I8 {
+            value: context.i8_type().const_int(3, true),
+        };
+        let b = Value::I16 {
+            value: context.i16_type().const_int(5, true),
+        };
+
+        let (a, b, ty) = compiler.promote(a, b);
+        assert_eq!(ty, ValueType::I16);
+        assert!(matches!(a, Value::I16 { .. }));
+        assert!(matches!(b, Value::I16 { .. }));
+    }
+
+    #[test]
+    fn cast_to_same_type_is_a_no_op() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let value = Value::F32 {
+            value: context.f32_type().const_float(2.0),
+        };
+        let cast = compiler.cast_to(value, ValueType::F32);
+        assert!(matches!(cast, Value::F32 { .. }));
+    }
+
+    #[test]
+    fn int_floor_div_matches_python_for_a_negative_dividend() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = Value::I16 {
+            value: context.i16_type().const_int((-7i16) as u64, true),
+        };
+        let b = Value::I16 {
+            value: context.i16_type().const_int(2, true),
+        };
+
+        let quotient = compiler.compile_op(a, &ast::Operator::FloorDiv, b);
+        let remainder = compiler.compile_op(a, &ast::Operator::Mod, b);
+        match (quotient, remainder) {
+            (Value::I16 { value: q }, Value::I16 { value: r }) => {
+                assert_eq!(q.get_sign_extended_constant(), Some(-4));
+                assert_eq!(r.get_sign_extended_constant(), Some(1));
+            }
+            _ => panic!("expected I16 results"),
+        }
+    }
+
+    #[test]
+    fn int_floor_div_matches_python_for_a_positive_dividend() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = Value::I16 {
+            value: context.i16_type().const_int(7, true),
+        };
+        let b = Value::I16 {
+            value: context.i16_type().const_int(2, true),
+        };
+
+        let quotient = compiler.compile_op(a, &ast::Operator::FloorDiv, b);
+        let remainder = compiler.compile_op(a, &ast::Operator::Mod, b);
+        match (quotient, remainder) {
+            (Value::I16 { value: q }, Value::I16 { value: r }) => {
+                assert_eq!(q.get_sign_extended_constant(), Some(3));
+                assert_eq!(r.get_sign_extended_constant(), Some(1));
+            }
+            _ => panic!("expected I16 results"),
+        }
+    }
+
+    #[test]
+    fn float_floor_div_matches_python_for_a_negative_dividend() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = Value::F32 {
+            value: context.f32_type().const_float(-7.5),
+        };
+        let b = Value::F32 {
+            value: context.f32_type().const_float(2.0),
+        };
+
+        let quotient = compiler.compile_op(a, &ast::Operator::FloorDiv, b);
+        let remainder = compiler.compile_op(a, &ast::Operator::Mod, b);
+        match (quotient, remainder) {
+            (Value::F32 { value: q }, Value::F32 { value: r }) => {
+                assert_eq!(q.get_constant().map(|(v, _)| v), Some(-4.0));
+                assert_eq!(r.get_constant().map(|(v, _)| v), Some(0.5));
+            }
+            _ => panic!("expected F32 results"),
+        }
+    }
+
+    #[test]
+    fn float_floor_div_is_not_plain_division() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+
+        let a = Value::F32 {
+            value: context.f32_type().const_float(7.5),
+        };
+        let b = Value::F32 {
+            value: context.f32_type().const_float(2.0),
+        };
+
+        let quotient = compiler.compile_op(a, &ast::Operator::FloorDiv, b);
+        match quotient {
+            Value::F32 { value } => assert_eq!(value.get_constant().map(|(v, _)| v), Some(3.0)),
+            _ => panic!("expected an F32 result"),
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use inkwell::types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
+use inkwell::AddressSpace;
+use num_traits::ToPrimitive;
+use rustpython_parser::ast;
+
+use crate::compiler::error::{CompilerErrorReport, CompilerErrorType};
+use crate::compiler::{Compiler, Scope};
+use crate::irgen::expr::CGExpr;
+use crate::value::{ElementType, Value, ValueType};
+
+/// Statement-level codegen: function/return/assign/if, plus the
+/// annotation-to-`ValueType` and `ValueType`-to-LLVM-type lowering that
+/// every later expression feature (subscripting, keyword args, complex
+/// literals, ...) builds its functions and scopes on top of.
+pub trait CGStmt<'a, 'ctx> {
+    fn compile_stmt(&mut self, stmt: &ast::Statement);
+}
+
+impl<'a, 'ctx> CGStmt<'a, 'ctx> for Compiler<'a, 'ctx> {
+    fn compile_stmt(&mut self, stmt: &ast::Statement) {
+        self.set_source_location(stmt.location);
+
+        use ast::StatementType;
+        match &stmt.node {
+            StatementType::FunctionDef {
+                name, args, body, returns, ..
+            } => self.compile_function_def(name, args, body, returns.as_deref()),
+            StatementType::Return { value } => self.compile_return(value.as_ref()),
+            StatementType::Assign { targets, value } => self.compile_assign(targets, value),
+            StatementType::Expression { expression } => {
+                self.compile_expr(expression);
+            }
+            StatementType::If { test, body, orelse } => {
+                self.compile_if_stmt(test, body, orelse.as_ref())
+            }
+            StatementType::Pass => {}
+            _ => {
+                panic!(
+                    "{:?}\nNotImplemented statement {:?}",
+                    self.current_source_location, stmt.node,
+                );
+            }
+        }
+    }
+}
+
+impl<'a, 'ctx> Compiler<'a, 'ctx> {
+    /// Maps a Python type-hint expression to the `ValueType` it declares.
+    /// A missing annotation (no hint on `return`) means `Void`. A subscript
+    /// annotation (`elem[length]`, e.g. `int[4]`) declares a fixed-size
+    /// array of `elem`, with `length` a non-negative integer literal.
+    fn annotation_type(&self, annotation: Option<&ast::Expression>) -> ValueType {
+        let annotation = match annotation {
+            Some(annotation) => annotation,
+            None => return ValueType::Void,
+        };
+
+        match &annotation.node {
+            ast::ExpressionType::Identifier { name } => match name.as_str() {
+                "int" => ValueType::I16,
+                "float" => ValueType::F32,
+                "str" => ValueType::Str,
+                "bool" => ValueType::Bool,
+                "complex" => ValueType::Complex,
+                other => panic!("{}", self.errs(CompilerErrorType::TypeError(format!(
+                    "unknown type annotation '{}'",
+                    other
+                )))),
+            },
+            ast::ExpressionType::Subscript { a, b } => {
+                ValueType::Array {
+                    element: self.annotation_element_type(a),
+                    length: self.annotation_array_length(b),
+                }
+            }
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "type annotations must be a simple name or 'elem[length]'".to_string()
+            ))),
+        }
+    }
+
+    /// The element-type half of an array annotation, e.g. the `int` in
+    /// `int[4]`. `ElementType` has no `complex` variant, so `complex[n]`
+    /// is rejected the same as any other unknown name.
+    fn annotation_element_type(&self, annotation: &ast::Expression) -> ElementType {
+        match &annotation.node {
+            ast::ExpressionType::Identifier { name } => match name.as_str() {
+                "int" => ElementType::I16,
+                "float" => ElementType::F32,
+                "str" => ElementType::Str,
+                "bool" => ElementType::Bool,
+                other => panic!("{}", self.errs(CompilerErrorType::TypeError(format!(
+                    "unknown array element type '{}'",
+                    other
+                )))),
+            },
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "array element type must be a simple name".to_string()
+            ))),
+        }
+    }
+
+    /// The length half of an array annotation, e.g. the `4` in `int[4]`.
+    fn annotation_array_length(&self, length: &ast::Expression) -> u32 {
+        match &length.node {
+            ast::ExpressionType::Number {
+                value: ast::Number::Integer { value },
+            } => value.to_u32().unwrap_or_else(|| {
+                panic!("{}", self.errs(CompilerErrorType::TypeError(format!(
+                    "array length {} does not fit in a u32",
+                    value
+                ))))
+            }),
+            _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "array length must be a non-negative integer literal".to_string()
+            ))),
+        }
+    }
+
+    /// The LLVM type a `ValueType` lowers to when it appears in a function
+    /// signature. `Void` isn't valid there (it's handled separately for
+    /// return types). An array lowers to a pointer to its element type,
+    /// matching the pointer-to-first-element `Value::Array` already carries.
+    fn llvm_basic_type(&self, ty: ValueType) -> BasicTypeEnum<'ctx> {
+        match ty {
+            ValueType::Bool => self.context.bool_type().into(),
+            ValueType::I8 => self.context.i8_type().into(),
+            ValueType::I16 => self.context.i16_type().into(),
+            ValueType::F32 => self.context.f32_type().into(),
+            ValueType::Str => self
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .into(),
+            ValueType::Complex => self.complex_type().into(),
+            ValueType::Array { element, .. } => self
+                .llvm_basic_type(element.as_value_type())
+                .ptr_type(AddressSpace::Generic)
+                .into(),
+            ValueType::Void => {
+                panic!("{}", self.errs(CompilerErrorType::TypeError(
+                    "type is not valid in a function signature".to_string()
+                )))
+            }
+        }
+    }
+
+    fn compile_function_def(
+        &mut self,
+        name: &str,
+        args: &ast::Parameters,
+        body: &ast::Suite,
+        returns: Option<&ast::Expression>,
+    ) {
+        let param_types: Vec<ValueType> = args
+            .args
+            .iter()
+            .map(|param| self.annotation_type(param.annotation.as_deref()))
+            .collect();
+        let param_names: Vec<String> = args.args.iter().map(|param| param.arg.clone()).collect();
+        let return_type = self.annotation_type(returns);
+
+        let param_llvm_types: Vec<BasicMetadataTypeEnum> = param_types
+            .iter()
+            .map(|ty| self.llvm_basic_type(*ty).into())
+            .collect();
+        let fn_type = match return_type {
+            ValueType::Void => self.context.void_type().fn_type(&param_llvm_types, false),
+            other => self.llvm_basic_type(other).fn_type(&param_llvm_types, false),
+        };
+
+        let function = self.module.add_function(name, fn_type, None);
+        self.register_function(name.to_string(), function, param_names.clone());
+
+        let entry = self.context.append_basic_block(function, "entry");
+        self.builder.position_at_end(entry);
+
+        let prev_fn = self.fn_value_opt.replace(function);
+        let mut scope: Scope<'ctx> = Scope::new();
+
+        for (i, (param_name, param_ty)) in param_names.iter().zip(param_types.iter()).enumerate() {
+            let param_value = function.get_nth_param(i as u32).unwrap();
+            // An array parameter already *is* the pointer `load_variable`
+            // expects a `ValueType::Array` binding to hold; alloca'ing and
+            // storing it would instead bind the variable to a pointer to
+            // that pointer.
+            let slot = if let ValueType::Array { .. } = param_ty {
+                param_value.into_pointer_value()
+            } else {
+                let alloca = self
+                    .builder
+                    .build_alloca(param_value.get_type(), param_name);
+                self.builder.build_store(alloca, param_value);
+                alloca
+            };
+            scope.insert(param_name.clone(), (*param_ty, slot));
+        }
+        self.fn_scope.insert(function, scope);
+
+        for statement in body {
+            self.compile_stmt(statement);
+        }
+
+        if self
+            .builder
+            .get_insert_block()
+            .unwrap()
+            .get_terminator()
+            .is_none()
+        {
+            self.builder.build_return(None);
+        }
+
+        self.fn_value_opt = prev_fn;
+    }
+
+    fn compile_return(&mut self, value: Option<&ast::Expression>) {
+        match value.map(|expr| self.compile_expr(expr)) {
+            Some(Value::Void) | None => {
+                self.builder.build_return(None);
+            }
+            Some(value) => {
+                let basic = value.as_basic_value();
+                self.builder.build_return(Some(&basic));
+            }
+        }
+    }
+
+    fn compile_assign(&mut self, targets: &[ast::Expression], value: &ast::Expression) {
+        let value = self.compile_expr(value);
+        for target in targets {
+            match &target.node {
+                ast::ExpressionType::Identifier { name } => self.store_variable(name, value),
+                _ => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                    "assignment target must be a simple name".to_string()
+                ))),
+            }
+        }
+    }
+
+    /// Stores to a local, declaring it with an `alloca` in the current
+    /// function's scope on first assignment. Module-level assignment
+    /// (outside any function) isn't implemented yet, matching the
+    /// "TODO: Global string builder" gap for top-level string constants.
+    ///
+    /// `Value::Array` is special-cased exactly like a `compile_function_def`
+    /// array parameter: the scope entry is bound straight to the buffer's
+    /// own pointer rather than `alloca`'d and stored, since `load_variable`
+    /// treats an `Array` scope pointer as the buffer's base address itself,
+    /// not a slot holding it. Skipping this would bind the name to a fresh
+    /// pointer-to-pointer slot instead of the buffer.
+    fn store_variable(&mut self, name: &str, value: Value<'ctx>) {
+        let fn_value = match self.fn_value_opt {
+            Some(fn_value) => fn_value,
+            None => panic!("{}", self.errs(CompilerErrorType::TypeError(
+                "module-level assignment is not implemented".to_string()
+            ))),
+        };
+
+        if let Some((ty, pointer)) = self
+            .fn_scope
+            .get(&fn_value)
+            .and_then(|scope| scope.get(name))
+            .copied()
+        {
+            if ty != value.get_type() {
+                panic!("{}", self.errs(CompilerErrorType::TypeError(format!(
+                    "cannot assign {:?} to '{}' of type {:?}",
+                    value.get_type(),
+                    name,
+                    ty
+                ))));
+            }
+            match value {
+                Value::Array { value: buffer, .. } => {
+                    self.fn_scope
+                        .get_mut(&fn_value)
+                        .unwrap()
+                        .insert(name.to_string(), (ty, buffer));
+                }
+                _ => {
+                    self.builder.build_store(pointer, value.as_basic_value());
+                }
+            }
+            return;
+        }
+
+        let slot = match value {
+            Value::Array { value: buffer, .. } => buffer,
+            _ => {
+                let basic = value.as_basic_value();
+                let alloca = self.builder.build_alloca(basic.get_type(), name);
+                self.builder.build_store(alloca, basic);
+                alloca
+            }
+        };
+        self.fn_scope
+            .get_mut(&fn_value)
+            .unwrap()
+            .insert(name.to_string(), (value.get_type(), slot));
+    }
+
+    fn compile_if_stmt(
+        &mut self,
+        test: &ast::Expression,
+        body: &ast::Suite,
+        orelse: Option<&ast::Suite>,
+    ) {
+        let cond = self.compile_expr(test);
+        self.gen_if(
+            cond,
+            |c| {
+                for statement in body {
+                    c.compile_stmt(statement);
+                }
+                Value::Void
+            },
+            |c| {
+                if let Some(orelse) = orelse {
+                    for statement in orelse {
+                        c.compile_stmt(statement);
+                    }
+                }
+                Value::Void
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::irgen::test_support::compiler_at_entry;
+    use inkwell::context::Context;
+    use num_bigint::BigInt;
+
+    fn identifier(name: &str) -> ast::Expression {
+        ast::Expression {
+            location: ast::Location::new(1, 1),
+            node: ast::ExpressionType::Identifier {
+                name: name.to_string(),
+            },
+        }
+    }
+
+    fn int_literal(n: u32) -> ast::Expression {
+        ast::Expression {
+            location: ast::Location::new(1, 1),
+            node: ast::ExpressionType::Number {
+                value: ast::Number::Integer {
+                    value: BigInt::from(n),
+                },
+            },
+        }
+    }
+
+    fn array_annotation(elem: &str, length: u32) -> ast::Expression {
+        ast::Expression {
+            location: ast::Location::new(1, 1),
+            node: ast::ExpressionType::Subscript {
+                a: Box::new(identifier(elem)),
+                b: Box::new(int_literal(length)),
+            },
+        }
+    }
+
+    #[test]
+    fn annotation_type_resolves_array_subscript() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let compiler = Compiler::new("test".to_string(), &context, &builder, &module);
+
+        let ty = compiler.annotation_type(Some(&array_annotation("int", 4)));
+        assert_eq!(
+            ty,
+            ValueType::Array {
+                element: ElementType::I16,
+                length: 4,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "TypeError")]
+    fn annotation_type_rejects_unknown_array_element() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let compiler = Compiler::new("test".to_string(), &context, &builder, &module);
+
+        compiler.annotation_type(Some(&array_annotation("complex", 4)));
+    }
+
+    #[test]
+    fn llvm_basic_type_lowers_array_to_a_pointer() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let compiler = Compiler::new("test".to_string(), &context, &builder, &module);
+
+        let llvm_ty = compiler.llvm_basic_type(ValueType::Array {
+            element: ElementType::F32,
+            length: 8,
+        });
+        assert!(llvm_ty.is_pointer_type());
+    }
+
+    /// Regression test for a bug where aliasing an array into a new local
+    /// (`b = buf`) went through the generic `alloca` + `store` path, which
+    /// binds the name to a fresh pointer-to-pointer slot instead of the
+    /// buffer itself. `store_variable` must bind `Array` scope entries
+    /// straight to the incoming pointer, exactly like a `compile_function_def`
+    /// array parameter.
+    #[test]
+    fn store_variable_aliases_an_array_without_an_extra_alloca() {
+        let context = Context::create();
+        let builder = context.create_builder();
+        let module = context.create_module("test");
+        let mut compiler = compiler_at_entry(&context, &builder, &module);
+        let function = compiler.fn_value();
+        compiler.fn_scope.insert(function, Scope::new());
+
+        let buffer = context
+            .i16_type()
+            .ptr_type(AddressSpace::Generic)
+            .const_null();
+        let array = Value::Array {
+            value: buffer,
+            element: ElementType::I16,
+            length: 4,
+        };
+
+        compiler.store_variable("buf", array);
+
+        let (ty, pointer) = compiler.fn_scope[&function].get("buf").copied().unwrap();
+        assert_eq!(
+            ty,
+            ValueType::Array {
+                element: ElementType::I16,
+                length: 4,
+            }
+        );
+        assert_eq!(pointer, buffer);
+    }
+}
@@ -0,0 +1,24 @@
+//! Shared fixtures for `irgen` unit tests.
+
+#![cfg(test)]
+
+use inkwell::context::Context;
+
+use crate::compiler::Compiler;
+
+/// Most `irgen` codegen emits builder instructions inside a function body,
+/// so the compiler under test needs a positioned insertion point rather
+/// than a bare module.
+pub(crate) fn compiler_at_entry<'ctx>(
+    context: &'ctx Context,
+    builder: &'ctx inkwell::builder::Builder<'ctx>,
+    module: &'ctx inkwell::module::Module<'ctx>,
+) -> Compiler<'ctx, 'ctx> {
+    let fn_type = context.void_type().fn_type(&[], false);
+    let function = module.add_function("test_fn", fn_type, None);
+    let entry = context.append_basic_block(function, "entry");
+    builder.position_at_end(entry);
+    let mut compiler = Compiler::new("test".to_string(), context, builder, module);
+    compiler.fn_value_opt = Some(function);
+    compiler
+}
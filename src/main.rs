@@ -2,6 +2,8 @@ use std::error::Error;
 use std::fs::{read_to_string, remove_file};
 use std::io::Write;
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 use clap::{App, Arg, ArgMatches};
 use inkwell::context::Context;
@@ -32,7 +34,8 @@ fn parse_arguments<'a>(app: App<'a, '_>) -> ArgMatches<'a> {
             Arg::with_name("file")
                 .index(2)
                 .required(true)
-                .help("Source file path"),
+                .multiple(true)
+                .help("Source file path(s)"),
         )
         .arg(
             Arg::with_name("emit-llvm")
@@ -54,7 +57,10 @@ fn parse_arguments<'a>(app: App<'a, '_>) -> ArgMatches<'a> {
         .get_matches()
 }
 
-fn build<'a, 'ctx>(pkg: &str, no_opt: bool) -> String {
+/// Compiles a single source file into its own LLVM module. Each call
+/// creates its own `Context`, so this is safe to run concurrently from
+/// multiple worker threads as long as no two threads share a `Context`.
+fn compile_file(pkg: &str, no_opt: bool) -> String {
     let ctx = Context::create();
     let module = ctx.create_module(pkg);
 
@@ -99,6 +105,109 @@ fn build<'a, 'ctx>(pkg: &str, no_opt: bool) -> String {
     }
 }
 
+/// Number of worker threads to compile with, read from `NUM_JOBS` and
+/// falling back to the available CPU count when unset or invalid.
+fn num_jobs() -> usize {
+    std::env::var("NUM_JOBS")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        })
+}
+
+/// Runs `f` over `items` across a pool of `workers` threads, and returns the
+/// results in the same order as `items` regardless of which worker finished
+/// which item first. Pulled out of `build` so the ordering guarantee can be
+/// unit-tested without going through a real compile.
+fn parallel_map<T, R, F>(items: Vec<T>, workers: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Send + Sync,
+{
+    let workers = workers.min(items.len().max(1));
+    let queue = Arc::new(Mutex::new(
+        items.into_iter().enumerate().rev().collect::<Vec<_>>(),
+    ));
+    let f = Arc::new(f);
+
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let f = Arc::clone(&f);
+                scope.spawn(move || {
+                    let mut results = Vec::new();
+                    loop {
+                        let (index, item) = match queue.lock().unwrap().pop() {
+                            Some(entry) => entry,
+                            None => break,
+                        };
+                        results.push((index, f(item)));
+                    }
+                    results
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("a worker thread panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Compiles every source file into its own LLVM module, in parallel,
+/// using a worker pool sized by `num_jobs`. Each file is lowered
+/// independently rather than linked into a single module, and the
+/// resulting assembly paths are emitted side by side for the linker stage,
+/// in the same order the files were given on the command line.
+fn build(files: &[String], no_opt: bool) -> Vec<String> {
+    parallel_map(files.to_vec(), num_jobs(), |file| {
+        compile_file(&file, no_opt)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Regression test for a bug where results came back in whichever order
+    /// workers finished, not the order `items` was given in. Items near the
+    /// front of the queue are given the longest artificial delay, so a
+    /// worker pool that didn't track original position would return them
+    /// last.
+    #[test]
+    fn parallel_map_preserves_input_order_despite_out_of_order_completion() {
+        let items: Vec<u64> = (0..8).collect();
+        let results = parallel_map(items.clone(), 4, |n| {
+            thread::sleep(Duration::from_millis((8 - n) * 2));
+            n
+        });
+        assert_eq!(results, items);
+    }
+
+    #[test]
+    fn parallel_map_handles_more_workers_than_items() {
+        let results = parallel_map(vec!["a", "b"], 8, |s| s.to_uppercase());
+        assert_eq!(results, vec!["A".to_string(), "B".to_string()]);
+    }
+
+    #[test]
+    fn parallel_map_handles_empty_input() {
+        let results: Vec<i32> = parallel_map(vec![], 4, |n: i32| n);
+        assert!(results.is_empty());
+    }
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let app = App::new("dsp" /* TODO: dspython */)
         .version(VERSION)
@@ -109,7 +218,11 @@ fn main() -> Result<(), Box<dyn Error>> {
     let matches = parse_arguments(app);
 
     let command = matches.value_of("command").unwrap();
-    let file = matches.value_of("file").unwrap();
+    let files: Vec<String> = matches
+        .values_of("file")
+        .unwrap()
+        .map(String::from)
+        .collect();
     let emit_llvm = matches.is_present("emit-llvm");
     let no_opt = matches.is_present("no-opt");
 
@@ -124,8 +237,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         panic!(format!("Unknown command '{}'", command));
     }
 
-    // Build assembly from python file
-    let assembly = build(file, no_opt);
+    // Build assembly from the python source files, one module per file,
+    // compiled concurrently across a `NUM_JOBS`-sized worker pool.
+    let assemblies = build(&files, no_opt);
 
     // Linker path
     let linker = if cfg!(debug_assertions) {
@@ -134,15 +248,17 @@ fn main() -> Result<(), Box<dyn Error>> {
         "bin/builder"
     };
 
-    // Execute the linker command
+    // Execute the linker command, passing every module's assembly
     let out = if cfg!(target_os = "windows") {
         Command::new("cmd")
-            .args(&["/C", linker, arduino_dir, assembly.as_str()])
+            .args(&["/C", linker, arduino_dir])
+            .args(assemblies.iter())
             .status()
             .expect("Failed to execute command")
     } else {
         Command::new("sh")
-            .args(&["-c", linker, arduino_dir, assembly.as_str()])
+            .args(&["-c", linker, arduino_dir])
+            .args(assemblies.iter())
             .status()
             .expect("Failed to execute command")
     };
@@ -152,7 +268,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     if command == "flash" || command == "upload" {
         let port = matches.value_of("port").expect("Port not provided!");
-        print!("{} << {}...", port, file);
+        print!("{} << {}...", port, files.join(", "));
         std::io::stdout().flush().unwrap_or_default();
 
         // Uploader path
@@ -162,8 +278,9 @@ fn main() -> Result<(), Box<dyn Error>> {
             "bin/flash"
         };
 
-        // Hex file path
-        let hex_file = &(assembly.to_owned() + ".hex");
+        // The builder links every module's assembly into a single hex
+        // file, named after the first module's assembly.
+        let hex_file = &(assemblies[0].to_owned() + ".hex");
 
         // Execute the uploader command
         let out = if cfg!(target_os = "windows") {
@@ -186,9 +303,11 @@ fn main() -> Result<(), Box<dyn Error>> {
         println!("[Done]")
     }
 
-    // Remove the assembly file if flag `emit-llvm` is not enabled
+    // Remove the assembly files if flag `emit-llvm` is not enabled
     if !emit_llvm {
-        remove_file(&assembly).unwrap();
+        for assembly in &assemblies {
+            remove_file(assembly).unwrap();
+        }
     }
 
     Ok(())
@@ -0,0 +1,152 @@
+pub mod convert;
+
+use inkwell::values::{BasicValueEnum, FloatValue, IntValue, PointerValue, StructValue};
+
+/// The scalar type carried by an array/buffer element. Kept separate from
+/// `ValueType` (rather than boxing a `ValueType` recursively) so arrays of
+/// arrays are simply not expressible, and both types can stay `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementType {
+    Bool,
+    I8,
+    I16,
+    F32,
+    Str,
+}
+
+impl ElementType {
+    pub fn as_value_type(self) -> ValueType {
+        match self {
+            ElementType::Bool => ValueType::Bool,
+            ElementType::I8 => ValueType::I8,
+            ElementType::I16 => ValueType::I16,
+            ElementType::F32 => ValueType::F32,
+            ElementType::Str => ValueType::Str,
+        }
+    }
+}
+
+/// The type of a compiled value, used to drive codegen dispatch and
+/// argument/return coercion independently of the underlying LLVM type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Void,
+    Bool,
+    I8,
+    I16,
+    F32,
+    Str,
+    /// A fixed-size array/buffer; the length is known at compile time so
+    /// constant-index folding and bounds checks don't need a runtime load.
+    Array {
+        element: ElementType,
+        length: u32,
+    },
+    /// A complex number, backed by an LLVM `{ f32, f32 }` struct.
+    Complex,
+}
+
+/// A compiled expression result, tagged with enough type information to
+/// drive further codegen (casts, calls, operator dispatch) without having
+/// to re-inspect the underlying LLVM value.
+#[derive(Debug, Clone, Copy)]
+pub enum Value<'ctx> {
+    Void,
+    Bool {
+        value: IntValue<'ctx>,
+    },
+    I8 {
+        value: IntValue<'ctx>,
+    },
+    I16 {
+        value: IntValue<'ctx>,
+    },
+    F32 {
+        value: FloatValue<'ctx>,
+    },
+    Str {
+        value: PointerValue<'ctx>,
+    },
+    /// Pointer to the first element of a fixed-size array/buffer.
+    Array {
+        value: PointerValue<'ctx>,
+        element: ElementType,
+        length: u32,
+    },
+    /// A `{ f32, f32 }` struct holding the real and imaginary parts;
+    /// packed/unpacked by `Compiler::pack_complex`/`unpack_complex` since
+    /// that requires a builder to emit the `insert`/`extract_value`s.
+    Complex {
+        value: StructValue<'ctx>,
+    },
+}
+
+impl<'ctx> Value<'ctx> {
+    pub fn get_type(&self) -> ValueType {
+        match self {
+            Value::Void => ValueType::Void,
+            Value::Bool { .. } => ValueType::Bool,
+            Value::I8 { .. } => ValueType::I8,
+            Value::I16 { .. } => ValueType::I16,
+            Value::F32 { .. } => ValueType::F32,
+            Value::Str { .. } => ValueType::Str,
+            Value::Array {
+                element, length, ..
+            } => ValueType::Array {
+                element: *element,
+                length: *length,
+            },
+            Value::Complex { .. } => ValueType::Complex,
+        }
+    }
+
+    /// Materializes a `Value` from a raw LLVM value of known type.
+    /// `load_variable` bypasses this for arrays when reading a variable,
+    /// since an array is kept as the pointer produced when it was declared
+    /// rather than re-derived from a loaded value. The `Array` arm here
+    /// still matters for every other caller, e.g. `gen_if` rewraps a `phi`
+    /// of two array pointers back into a `Value::Array` through this path.
+    pub fn from_basic_value(ty: ValueType, value: BasicValueEnum<'ctx>) -> Value<'ctx> {
+        match ty {
+            ValueType::Void => Value::Void,
+            ValueType::Bool => Value::Bool {
+                value: value.into_int_value(),
+            },
+            ValueType::I8 => Value::I8 {
+                value: value.into_int_value(),
+            },
+            ValueType::I16 => Value::I16 {
+                value: value.into_int_value(),
+            },
+            ValueType::F32 => Value::F32 {
+                value: value.into_float_value(),
+            },
+            ValueType::Str => Value::Str {
+                value: value.into_pointer_value(),
+            },
+            ValueType::Array { element, length } => Value::Array {
+                value: value.into_pointer_value(),
+                element,
+                length,
+            },
+            ValueType::Complex => Value::Complex {
+                value: value.into_struct_value(),
+            },
+        }
+    }
+
+    /// The inverse of `from_basic_value`: recovers the raw LLVM value,
+    /// e.g. to feed a `phi` node or pass an argument by its basic type.
+    pub fn as_basic_value(&self) -> BasicValueEnum<'ctx> {
+        match *self {
+            Value::Void => panic!("Void has no basic value representation"),
+            Value::Bool { value } => BasicValueEnum::IntValue(value),
+            Value::I8 { value } => BasicValueEnum::IntValue(value),
+            Value::I16 { value } => BasicValueEnum::IntValue(value),
+            Value::F32 { value } => BasicValueEnum::FloatValue(value),
+            Value::Str { value } => BasicValueEnum::PointerValue(value),
+            Value::Array { value, .. } => BasicValueEnum::PointerValue(value),
+            Value::Complex { value } => BasicValueEnum::StructValue(value),
+        }
+    }
+}
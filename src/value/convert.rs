@@ -0,0 +1,44 @@
+use num_bigint::BigInt;
+
+/// Truncates an arbitrary-precision Python integer literal down to the
+/// 64-bit, two's-complement representation `const_int` expects; values
+/// wider than the target's native widths are wrapped, not rejected.
+pub fn truncate_bigint_to_u64(value: &BigInt) -> u64 {
+    let mut bytes = value.to_signed_bytes_le();
+    let pad = if value.sign() == num_bigint::Sign::Minus {
+        0xffu8
+    } else {
+        0x00u8
+    };
+    bytes.resize(8, pad);
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+/// rustpython_parser hands us already-unescaped string contents; this
+/// just exposes them as an owned `String` for the builder to intern.
+pub fn try_get_constant_string(value: &str) -> Option<String> {
+    Some(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_through_small_values() {
+        assert_eq!(truncate_bigint_to_u64(&BigInt::from(0)), 0);
+        assert_eq!(truncate_bigint_to_u64(&BigInt::from(42)), 42);
+    }
+
+    #[test]
+    fn sign_extends_negative_values() {
+        assert_eq!(truncate_bigint_to_u64(&BigInt::from(-1)), u64::MAX);
+        assert_eq!(truncate_bigint_to_u64(&BigInt::from(-2)), u64::MAX - 1);
+    }
+
+    #[test]
+    fn wraps_values_wider_than_64_bits() {
+        let wide = BigInt::from(1) << 100;
+        assert_eq!(truncate_bigint_to_u64(&wide), 0);
+    }
+}